@@ -2,17 +2,44 @@ use std::io::prelude::*;
 use std::io::SeekFrom;
 use std::ffi::OsStr;
 use std::fs::File;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::{Arg, Command, CommandFactory, Parser};
 use clap::builder::{StringValueParser, TypedValueParser};
 use clap::error::{Error, ErrorKind};
 
+/// A byte offset, either absolute from the start of the input or — when the
+/// value is written with a leading `-` — relative to the end, the way `tail`
+/// counts bytes.
+#[derive(Clone, Copy, Debug)]
+enum Offset {
+    FromStart(u64),
+    FromEnd(u64),
+}
+
+impl Offset {
+    /// Resolves the offset against a known input length, clamping an
+    /// end-relative offset at 0. An end-relative offset needs a real length;
+    /// for streams of unknown length (`input_len == u64::MAX`) it is an error.
+    fn resolve(self, input_len: u64) -> u64 {
+        match self {
+            Offset::FromStart(n) => n,
+            Offset::FromEnd(k) => {
+                if input_len == u64::MAX {
+                    panic!("end-relative offsets require a file with \
+                            known length!");
+                }
+                input_len.saturating_sub(k)
+            },
+        }
+    }
+}
+
 #[derive(Clone)]
 struct PrefixedU64ValueParser;
 
 impl TypedValueParser for PrefixedU64ValueParser {
-    type Value = u64;
+    type Value = Offset;
 
     fn parse_ref(
         &self, cmd: &Command, arg: Option<&Arg>, value: &OsStr,
@@ -20,6 +47,12 @@ impl TypedValueParser for PrefixedU64ValueParser {
         let inner = StringValueParser::new();
         let num = inner.parse_ref(cmd, arg, value)?;
         let num = num.replace("_", "");
+        // a leading `-` flags the offset as measured from EOF; strip it first
+        // so the radix prefix handling below sees a bare number.
+        let (from_end, num) = match num.strip_prefix('-') {
+            Some(rest) => (true, rest.to_string()),
+            None => (false, num),
+        };
         let prefix = if num.len() < 2 { "" } else { &num[0..2] };
         let (num, base) = {
             match prefix {
@@ -31,17 +64,33 @@ impl TypedValueParser for PrefixedU64ValueParser {
         };
         match u64::from_str_radix(&num, base) {
             Err(e) => Err(Error::raw(ErrorKind::InvalidValue, e.to_string())),
-            Ok(o) => Ok(o),
+            Ok(o) if from_end => Ok(Offset::FromEnd(o)),
+            Ok(o) => Ok(Offset::FromStart(o)),
         }
     }
 }
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
-#[command(after_help = "no reading from stdin... for now")]
+#[command(after_help = "pass - as the input to read from stdin")]
 struct Args {
-    /// path of the file to read
-    input: PathBuf,
+    /// path(s) of the file(s) to read. use - for stdin. pass more than one to
+    /// pack their slices into a single bundle stream
+    #[arg(required = true, num_args = 1..)]
+    inputs: Vec<PathBuf>,
+
+    /// unpack a bundle produced from multiple inputs back onto disk
+    #[arg(short = 'x', long)]
+    extract: bool,
+
+    /// interpret --start, --end and -n as line numbers instead of byte
+    /// offsets (head/tail/sed -n style)
+    #[arg(short, long)]
+    lines: bool,
+
+    /// allow --lines on input that looks binary
+    #[arg(long)]
+    force: bool,
 
     /// file to output to. default: stdout
     #[arg(short, long)]
@@ -49,104 +98,38 @@ struct Args {
 
     /// number of bytes to read. default: all
     #[arg(short = 'n', long, value_parser = PrefixedU64ValueParser)]
-    bytes: Option<u64>,
+    bytes: Option<Offset>,
 
-    /// byte to start reading at (inclusive). default: 0
-    #[arg(short, long, value_parser = PrefixedU64ValueParser)]
-    start: Option<u64>,
+    /// byte to start reading at (inclusive). a leading - counts back from
+    /// EOF, e.g. -512 for the last 512 bytes. default: 0
+    #[arg(short, long, allow_hyphen_values = true,
+          value_parser = PrefixedU64ValueParser)]
+    start: Option<Offset>,
 
-    /// byte to stop reading at (exclusive). default: last byte
-    #[arg(short, long, value_parser = PrefixedU64ValueParser)]
-    end: Option<u64>,
+    /// byte to stop reading at (exclusive). a leading - counts back from EOF,
+    /// e.g. -1 to drop the last byte. default: last byte
+    #[arg(short, long, allow_hyphen_values = true,
+          value_parser = PrefixedU64ValueParser)]
+    end: Option<Offset>,
 }
 
 fn main() {
     let args = Args::command().get_matches();
 
     // input is required, unwrap shouldn't fail
-    let input = args.get_one::<PathBuf>("input").unwrap();
-    {
-        let input_path = input.as_path();
-        if !input_path.exists() {
-            let input_path = input_path.to_str();
-            match input_path {
-                Some(o) => panic!("invalid path {o}!"),
-                None => panic!("specified path was invalid UTF-8!"),
-            };
-        }
-    }
-    let mut input = File::open(input).expect("error opening input file!");
-
-    let (start, bytes) = {
-        let input_len =
-            input.metadata().expect("error reading file metadata!").len();
-
-        // grab all the relevant option values from clap,
-        // toss out all the options that weren't specified,
-        // unwrap the rest into tuples of form (option_name, option_value)
-        let mut opt_stack: Vec<(&str, u64)> =
-            ["start", "bytes", "end"]
-            .into_iter()
-            .map(|o| (o, args.get_one::<u64>(o)))
-            .filter(|(_, v)| v.is_some())
-            .map(|(k, v)| (k, *v.unwrap()))
-            .collect();
+    let inputs: Vec<&PathBuf> =
+        args.get_many::<PathBuf>("inputs").unwrap().collect();
 
-        {
-            // the spicy fold checks for the presence of the start and bytes
-            // flags. it effectively has two accumulators, one (a_n) to hold
-            // the number of values it finds, and one (a_v) to hold the value
-            // of start + bytes, should it read both opts (i.e. if a_n == 2)
-            let (opt_count, start_bytes) =
-                opt_stack
-                .iter()
-                .fold(
-                    (0, 0),
-                    |(a_n, a_v), (k, v)| {
-                        if *k == "start" || *k == "bytes" { (a_n + 1, a_v + v) }
-                        else { (a_n, a_v) }
-                    }
-                );
-            if opt_count == 2 {
-                opt_stack.insert(2, ("start + bytes", start_bytes));
-            }
+    // extraction reads a single bundle back onto disk and ignores the
+    // byte-range flags entirely, so handle it before anything else.
+    if args.get_flag("extract") {
+        if inputs.len() != 1 {
+            panic!("--extract takes exactly one bundle as input!");
         }
-
-        opt_stack.push(("input file size", input_len));
-        opt_stack.sort_by(|(_, a), (_, b)| a.cmp(b));
-        if let Some((k, _)) = opt_stack.pop() {
-            if k != "input file size" {
-                panic!("value of {k} cannot exceed input file size!");
-            }
-        };
-
-        // if "end" is not specified, we read to EOF. if it is, we read up to
-        // (but not including) the specified location. this means that a valid
-        // "end" value would become the new effective input length.
-        let input_len = {
-            if let Some(_) = opt_stack.iter().find(|(k, _)| *k == "end") {
-                match opt_stack.pop().unwrap() {
-                    (k, _) if k != "end" => {
-                        panic!("value of {k} cannot exceed value of end!");
-                    },
-                    (_, v) => v,
-                }
-            } else { input_len }
-        };
-
-        match opt_stack.pop() {
-            Some((k, _)) if k == "start + bytes" => {
-                match opt_stack[0..2] {
-                    [(k, start), (_, bytes)] if k == "start" => (start, bytes),
-                    [(k, bytes), (_, start)] if k == "bytes" => (start, bytes),
-                    _ => panic!("forbidden error! pls file a bug report!"),
-                }
-            },
-            Some((k, start)) if k == "start" => (start, input_len - start),
-            Some((k, bytes)) if k == "bytes" => (0, bytes),
-            _ => (0, input_len),
-        }
-    };
+        let (mut input, _) = open_input(inputs[0]);
+        extract(&mut input).unwrap();
+        return;
+    }
 
     let mut output: Box<dyn Write> = {
         match args.get_one::<PathBuf>("output") {
@@ -162,19 +145,561 @@ fn main() {
         }
     };
 
-    slice(bytes, start, &mut input, &mut output).unwrap();
+    // a single input streams straight through; multiple inputs get wrapped
+    // in a self-describing bundle so they can be reconstructed with
+    // --extract. each entry is recorded under its input path as given, and
+    // --extract refuses absolute paths and paths containing `..`.
+    if inputs.len() != 1 {
+        if args.get_flag("lines") {
+            panic!("--lines only works with a single input!");
+        }
+        bundle(&args, &inputs, &mut output).unwrap();
+        return;
+    }
+
+    let (mut input, file_len) = open_input(inputs[0]);
+    if args.get_flag("lines") {
+        line_slice(&args, &mut input, file_len, &mut output).unwrap();
+    } else {
+        let input_len = file_len.unwrap_or(u64::MAX);
+        let (s, b, e) = resolved_offsets(&args, input_len);
+        let (start, bytes) = resolve_range(s, b, e, input_len);
+        slice(bytes, start, &mut input, &mut output).unwrap();
+    }
+}
+
+/// Opens an input path into an [`Input`]. A regular file can be seeked
+/// directly; anything else (stdin, a pipe, a fifo) has to be seeked by
+/// discarding bytes and has no known length.
+fn open_input(input_arg: &PathBuf) -> (Input, Option<u64>) {
+    if input_arg.as_os_str() == "-" {
+        return (Input::Stream(Box::new(std::io::stdin())), None);
+    }
+    let input_path = input_arg.as_path();
+    if !input_path.exists() {
+        match input_path.to_str() {
+            Some(o) => panic!("invalid path {o}!"),
+            None => panic!("specified path was invalid UTF-8!"),
+        };
+    }
+    let input = File::open(input_arg).expect("error opening input file!");
+    let meta = input.metadata().expect("error reading file metadata!");
+    if meta.file_type().is_file() {
+        (Input::File(input), Some(meta.len()))
+    } else {
+        (Input::Stream(Box::new(input)), None)
+    }
+}
+
+/// Pulls the `start`/`bytes`/`end` flags out of clap and resolves any
+/// end-relative offsets against `input_len`. `-n` is a count, not a position,
+/// so it may not be end-relative.
+fn resolved_offsets(
+    args: &clap::ArgMatches, input_len: u64,
+) -> (Option<u64>, Option<u64>, Option<u64>) {
+    let start = args.get_one::<Offset>("start").map(|o| o.resolve(input_len));
+    let end = args.get_one::<Offset>("end").map(|o| o.resolve(input_len));
+    let bytes = args.get_one::<Offset>("bytes").map(|o| match o {
+        Offset::FromStart(n) => *n,
+        Offset::FromEnd(_) => {
+            panic!("byte count (-n) cannot be relative to end of file!");
+        },
+    });
+    (start, bytes, end)
+}
+
+/// Resolves the `start`/`bytes`/`end` values against `input_len` into a
+/// concrete `(start, bytes)` pair. `input_len` should be [`u64::MAX`] for
+/// streams of unknown length.
+fn resolve_range(
+    start: Option<u64>, bytes: Option<u64>, end: Option<u64>, input_len: u64,
+) -> (u64, u64) {
+    // collect the options that were specified into tuples of the form
+    // (option_name, option_value), keeping the original start/bytes/end order
+    let mut opt_stack: Vec<(&str, u64)> =
+        [("start", start), ("bytes", bytes), ("end", end)]
+        .into_iter()
+        .filter(|(_, v)| v.is_some())
+        .map(|(k, v)| (k, v.unwrap()))
+        .collect();
+
+    {
+        // the spicy fold checks for the presence of the start and bytes
+        // flags. it effectively has two accumulators, one (a_n) to hold
+        // the number of values it finds, and one (a_v) to hold the value
+        // of start + bytes, should it read both opts (i.e. if a_n == 2)
+        let (opt_count, start_bytes) =
+            opt_stack
+            .iter()
+            .fold(
+                (0, 0),
+                |(a_n, a_v), (k, v)| {
+                    if *k == "start" || *k == "bytes" { (a_n + 1, a_v + v) }
+                    else { (a_n, a_v) }
+                }
+            );
+        if opt_count == 2 {
+            opt_stack.insert(2, ("start + bytes", start_bytes));
+        }
+    }
+
+    opt_stack.push(("input file size", input_len));
+    opt_stack.sort_by(|(_, a), (_, b)| a.cmp(b));
+    if let Some((k, _)) = opt_stack.pop() {
+        if k != "input file size" {
+            panic!("value of {k} cannot exceed input file size!");
+        }
+    };
+
+    // if "end" is not specified, we read to EOF. if it is, we read up to
+    // (but not including) the specified location. this means that a valid
+    // "end" value would become the new effective input length.
+    let input_len = {
+        if let Some(_) = opt_stack.iter().find(|(k, _)| *k == "end") {
+            match opt_stack.pop().unwrap() {
+                (k, _) if k != "end" => {
+                    panic!("value of {k} cannot exceed value of end!");
+                },
+                (_, v) => v,
+            }
+        } else { input_len }
+    };
+
+    match opt_stack.pop() {
+        Some((k, _)) if k == "start + bytes" => {
+            match opt_stack[0..2] {
+                [(k, start), (_, bytes)] if k == "start" => (start, bytes),
+                [(k, bytes), (_, start)] if k == "bytes" => (start, bytes),
+                _ => panic!("forbidden error! pls file a bug report!"),
+            }
+        },
+        Some((k, start)) if k == "start" => (start, input_len - start),
+        Some((k, bytes)) if k == "bytes" => (0, bytes),
+        _ => (0, input_len),
+    }
+}
+
+/// An input source `slice()` can read from. A regular file supports a real
+/// `Seek`; a plain stream (stdin, a pipe) is "seeked" by reading and
+/// discarding the skipped bytes.
+enum Input {
+    File(File),
+    Stream(Box<dyn Read>),
+}
+
+impl Input {
+    /// Advances the input to byte `start`: a direct seek for files, or
+    /// draining `start` bytes into a throwaway buffer for streams.
+    fn seek_to(&mut self, start: u64) -> std::io::Result<()> {
+        match self {
+            Input::File(f) => {
+                if start > 0 { f.seek(SeekFrom::Start(start))?; }
+                Ok(())
+            },
+            Input::Stream(r) => {
+                let mut scratch = [0u8; 4096];
+                let mut remaining = start;
+                while remaining > 0 {
+                    let want = remaining.min(scratch.len() as u64) as usize;
+                    let read = r.read(&mut scratch[..want])?;
+                    if read == 0 { break; }
+                    remaining -= read as u64;
+                }
+                Ok(())
+            },
+        }
+    }
+
+    /// Rewinds a seekable input back to its start. Only regular files can be
+    /// rewound; a stream has already been consumed and cannot be replayed.
+    fn rewind(&mut self) -> std::io::Result<()> {
+        match self {
+            Input::File(f) => { f.seek(SeekFrom::Start(0))?; Ok(()) },
+            Input::Stream(_) => panic!("cannot rewind a non-seekable input!"),
+        }
+    }
+}
+
+impl Read for Input {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Input::File(f) => f.read(buf),
+            Input::Stream(r) => r.read(buf),
+        }
+    }
 }
 
 /// Reads `bytes` bytes from the stream `input`, starting at byte `start` into
-/// the stream `output`.
-fn slice<R: Read + Seek, W: Write>(
-    bytes: u64, start: u64, input: &mut R, output: &mut W
+/// the stream `output`. Copies in fixed-size chunks so memory stays bounded
+/// regardless of how large the requested slice is.
+fn slice<W: Write>(
+    bytes: u64, start: u64, input: &mut Input, output: &mut W
 )-> std::io::Result<()> {
-    let mut data = Vec::with_capacity(bytes as usize);
-    {
-        if start > 0 { input.seek(SeekFrom::Start(start))?; }
-        input.take(bytes).read_to_end(&mut data)?;
+    input.seek_to(start)?;
+
+    let mut buf = [0u8; 65536];
+    let mut remaining = bytes;
+    while remaining > 0 {
+        let want = remaining.min(buf.len() as u64) as usize;
+        let read = input.read(&mut buf[..want])?;
+        if read == 0 { break; }
+        output.write_all(&buf[..read])?;
+        remaining -= read as u64;
     }
-    output.write_all(&data)?;
     Ok(())
 }
+
+/// Magic number every bundle stream starts with.
+const BUNDLE_MAGIC: [u8; 4] = *b"SLCE";
+/// Current bundle format version.
+const BUNDLE_VERSION: u32 = 1;
+
+/// Packs the slices of `inputs` into a single self-describing bundle written
+/// to `output`. The stream starts with [`BUNDLE_MAGIC`], a big-endian version
+/// and a big-endian header length, followed by one entry per file (sliced
+/// length as u64, path length as u32, UTF-8 path); the sliced payloads follow
+/// the header in the same order.
+fn bundle<W: Write>(
+    args: &clap::ArgMatches, inputs: &[&PathBuf], output: &mut W,
+) -> std::io::Result<()> {
+    // every entry records its sliced length before its payload, so each input
+    // needs a length we can measure without consuming the stream.
+    let mut plans: Vec<(Input, u64, u64)> = Vec::with_capacity(inputs.len());
+    let mut entries: Vec<(String, u64)> = Vec::with_capacity(inputs.len());
+    for input_arg in inputs {
+        if input_arg.as_os_str() == "-" {
+            panic!("stdin cannot be bundled; bundle inputs must be files!");
+        }
+        let path = match input_arg.to_str() {
+            Some(s) => s.to_string(),
+            None => panic!("bundle input path was invalid UTF-8!"),
+        };
+        let (input, file_len) = open_input(input_arg);
+        let file_len = match file_len {
+            Some(len) => len,
+            None => panic!("cannot bundle {path}: length is not known!"),
+        };
+        let (s, b, e) = resolved_offsets(args, file_len);
+        let (start, bytes) = resolve_range(s, b, e, file_len);
+        let len = bytes.min(file_len.saturating_sub(start));
+
+        entries.push((path, len));
+        plans.push((input, start, len));
+    }
+
+    let header = bundle_header(&entries);
+    output.write_all(&BUNDLE_MAGIC)?;
+    output.write_all(&BUNDLE_VERSION.to_be_bytes())?;
+    output.write_all(&(header.len() as u32).to_be_bytes())?;
+    output.write_all(&header)?;
+
+    for (mut input, start, len) in plans {
+        slice(len, start, &mut input, output)?;
+    }
+    Ok(())
+}
+
+/// Serialises one entry record per file — sliced length as a big-endian u64,
+/// path length as a big-endian u32, then the UTF-8 path — into the bundle
+/// header. Paired with [`parse_bundle_header`].
+fn bundle_header(entries: &[(String, u64)]) -> Vec<u8> {
+    let mut header = Vec::new();
+    for (path, len) in entries {
+        header.extend_from_slice(&len.to_be_bytes());
+        header.extend_from_slice(&(path.len() as u32).to_be_bytes());
+        header.extend_from_slice(path.as_bytes());
+    }
+    header
+}
+
+/// Walks a bundle header, pulling one `(sliced length, path)` entry record at
+/// a time. Inverse of [`bundle_header`].
+fn parse_bundle_header(header: &[u8]) -> Vec<(u64, PathBuf)> {
+    let mut entries: Vec<(u64, PathBuf)> = Vec::new();
+    let mut cursor = 0usize;
+    while cursor < header.len() {
+        let len = u64::from_be_bytes(
+            header[cursor..cursor + 8].try_into().unwrap());
+        cursor += 8;
+        let path_len = u32::from_be_bytes(
+            header[cursor..cursor + 4].try_into().unwrap()) as usize;
+        cursor += 4;
+        let path = std::str::from_utf8(&header[cursor..cursor + path_len])
+            .expect("bundle path was invalid UTF-8!");
+        cursor += path_len;
+        entries.push((len, PathBuf::from(path)));
+    }
+    entries
+}
+
+/// Rejects entry paths that could escape the extraction directory — absolute
+/// paths, a drive/root prefix, or any `..` component (zip-slip).
+fn is_safe_path(path: &Path) -> bool {
+    use std::path::Component;
+    !path.components().any(|c| matches!(
+        c,
+        Component::ParentDir | Component::RootDir | Component::Prefix(_),
+    ))
+}
+
+/// Unpacks a bundle produced by [`bundle`] from `input` into the current
+/// directory.
+fn extract(input: &mut Input) -> std::io::Result<()> {
+    extract_into(input, Path::new("."))
+}
+
+/// Unpacks a bundle from `input`, writing each entry to `base` joined with its
+/// recorded relative path and creating parent directories as needed. Paths
+/// that fail [`is_safe_path`] are refused before anything is written.
+fn extract_into(input: &mut Input, base: &Path) -> std::io::Result<()> {
+    let mut magic = [0u8; 4];
+    input.read_exact(&mut magic)?;
+    if magic != BUNDLE_MAGIC {
+        panic!("input is not a slice bundle!");
+    }
+
+    let version = read_u32(input)?;
+    if version != BUNDLE_VERSION {
+        panic!("unsupported bundle version {version}!");
+    }
+
+    let header_len = read_u32(input)? as usize;
+    let mut header = vec![0u8; header_len];
+    input.read_exact(&mut header)?;
+
+    let entries = parse_bundle_header(&header);
+    if let Some((_, path)) = entries.iter().find(|(_, p)| !is_safe_path(p)) {
+        panic!("refusing to extract unsafe path {}!", path.display());
+    }
+    for (len, path) in entries {
+        let dest = base.join(&path);
+        if let Some(parent) = dest.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let mut file = File::create(&dest)?;
+        slice(len, 0, input, &mut file)?;
+    }
+    Ok(())
+}
+
+/// Reads a big-endian `u32` from `input`.
+fn read_u32(input: &mut Input) -> std::io::Result<u32> {
+    let mut buf = [0u8; 4];
+    input.read_exact(&mut buf)?;
+    Ok(u32::from_be_bytes(buf))
+}
+
+/// How much of the input to sniff when deciding whether it is text.
+const SNIFF_BYTES: u64 = 8192;
+
+/// Guesses whether `prefix` is binary rather than text. A NUL byte is a strong
+/// signal; otherwise we call it binary when the prefix isn't valid UTF-8 (a
+/// multibyte sequence merely truncated at the end of the prefix still counts
+/// as text).
+fn looks_binary(prefix: &[u8]) -> bool {
+    if prefix.contains(&0) { return true; }
+    match std::str::from_utf8(prefix) {
+        Ok(_) => false,
+        Err(e) => e.error_len().is_some(),
+    }
+}
+
+/// Records the byte offset at which each line begins. The first line starts at
+/// 0; every byte following a `\n` starts another. A trailing `\n` therefore
+/// yields a final (empty) line starting at `len`.
+fn line_starts(input: &mut Input) -> std::io::Result<Vec<u64>> {
+    input.rewind()?;
+    let mut starts = vec![0u64];
+    let mut buf = [0u8; 65536];
+    let mut pos = 0u64;
+    loop {
+        let read = input.read(&mut buf)?;
+        if read == 0 { break; }
+        for (i, b) in buf[..read].iter().enumerate() {
+            if *b == b'\n' { starts.push(pos + i as u64 + 1); }
+        }
+        pos += read as u64;
+    }
+    Ok(starts)
+}
+
+/// Byte offset where line `line` begins, clamped to `len` for lines past EOF.
+fn line_offset(starts: &[u64], line: u64, len: u64) -> u64 {
+    *starts.get(line as usize).unwrap_or(&len)
+}
+
+/// Line-oriented slicing: translates the `start`/`bytes`/`end` flags, read as
+/// line numbers, into a byte `(start, bytes)` pair and hands off to [`slice`].
+/// Refuses binary input unless `--force` was given.
+fn line_slice<W: Write>(
+    args: &clap::ArgMatches, input: &mut Input, file_len: Option<u64>,
+    output: &mut W,
+) -> std::io::Result<()> {
+    let file_len = file_len
+        .expect("--lines needs a regular file to scan for line boundaries!");
+
+    // sniff a prefix before committing to a full scan
+    input.rewind()?;
+    let mut prefix = vec![0u8; file_len.min(SNIFF_BYTES) as usize];
+    input.read_exact(&mut prefix)?;
+    if looks_binary(&prefix) && !args.get_flag("force") {
+        panic!("input looks binary; pass --force to slice it by line anyway!");
+    }
+
+    // line_starts rewinds first, so the mid-file cursor left by the sniff is
+    // fine here; we rewind again below before the payload read.
+    let mut starts = line_starts(input)?;
+    // a trailing newline leaves a phantom empty line starting at EOF; drop it
+    // so line counts and EOF-relative (tail) offsets line up with head/tail.
+    if starts.len() > 1 && starts.last() == Some(&file_len) {
+        starts.pop();
+    }
+    let total_lines = starts.len() as u64;
+
+    // resolve EOF-relative line numbers against the real line count, but let
+    // the range itself run unbounded so asking for more lines than exist
+    // clamps to the whole file (head -n 100 style) instead of panicking.
+    let (s, b, e) = resolved_offsets(args, total_lines);
+    let (start_line, line_count) = resolve_range(s, b, e, u64::MAX);
+
+    let byte_start = line_offset(&starts, start_line, file_len);
+    let byte_end =
+        line_offset(&starts, start_line.saturating_add(line_count), file_len);
+
+    input.rewind()?;
+    slice(byte_end - byte_start, byte_start, input, output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // a fresh, process-unique scratch directory (no clock/rng available)
+    fn scratch_dir(tag: &str) -> PathBuf {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir()
+            .join(format!("slice-test-{}-{tag}-{n}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // assemble a full bundle stream from (path, payload) pairs
+    fn make_bundle(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let records: Vec<(String, u64)> = entries
+            .iter()
+            .map(|(p, data)| (p.to_string(), data.len() as u64))
+            .collect();
+        let header = bundle_header(&records);
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&BUNDLE_MAGIC);
+        buf.extend_from_slice(&BUNDLE_VERSION.to_be_bytes());
+        buf.extend_from_slice(&(header.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&header);
+        for (_, data) in entries { buf.extend_from_slice(data); }
+        buf
+    }
+
+    #[test]
+    fn bundle_round_trips_through_extract() {
+        let bytes = make_bundle(&[
+            ("a.txt", b"hello\n"),
+            ("sub/b.bin", &[0u8, 1, 2, 3]),
+        ]);
+        let dir = scratch_dir("round-trip");
+        let mut input = Input::Stream(Box::new(Cursor::new(bytes)));
+        extract_into(&mut input, &dir).unwrap();
+
+        assert_eq!(std::fs::read(dir.join("a.txt")).unwrap(), b"hello\n");
+        assert_eq!(
+            std::fs::read(dir.join("sub/b.bin")).unwrap(),
+            &[0u8, 1, 2, 3],
+        );
+    }
+
+    #[test]
+    fn is_safe_path_rejects_traversal() {
+        assert!(is_safe_path(Path::new("a.txt")));
+        assert!(is_safe_path(Path::new("sub/dir/a.txt")));
+        assert!(!is_safe_path(Path::new("../evil")));
+        assert!(!is_safe_path(Path::new("sub/../../evil")));
+        assert!(!is_safe_path(Path::new("/etc/passwd")));
+    }
+
+    #[test]
+    fn extract_refuses_malicious_header() {
+        let bytes = make_bundle(&[("../evil.txt", b"pwned")]);
+        let dir = scratch_dir("malicious");
+        let dest = dir.clone();
+        let result = std::panic::catch_unwind(move || {
+            let mut input = Input::Stream(Box::new(Cursor::new(bytes)));
+            extract_into(&mut input, &dest).unwrap();
+        });
+        assert!(result.is_err());
+        assert!(!dir.join("../evil.txt").exists());
+    }
+
+    #[test]
+    fn looks_binary_distinguishes_text_and_binary() {
+        assert!(!looks_binary(b"hello\nworld\n"));
+        assert!(!looks_binary("héllo wörld".as_bytes()));
+        // valid multibyte sequence cut off at the prefix boundary is still text
+        assert!(!looks_binary(&"é".as_bytes()[..1]));
+        assert!(looks_binary(b"text\0with nul"));
+        assert!(looks_binary(&[0xff, 0x28, 0x80, 0x41]));
+    }
+
+    // run `slice -l <extra...> <path>` and collect its output
+    fn run_lines(path: &Path, len: u64, extra: &[&str]) -> Vec<u8> {
+        let path_str = path.to_str().unwrap().to_string();
+        let mut argv = vec!["slice", "-l"];
+        argv.extend_from_slice(extra);
+        argv.push(path_str.as_str());
+        let matches = Args::command().get_matches_from(argv);
+        let (mut input, _) = open_input(&path.to_path_buf());
+        let mut out = Vec::new();
+        line_slice(&matches, &mut input, Some(len), &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn line_mode_returns_requested_lines() {
+        let dir = scratch_dir("lines");
+        let path = dir.join("lines.txt");
+        std::fs::write(&path, b"one\ntwo\nthree\nfour").unwrap();
+        let len = path.metadata().unwrap().len();
+
+        // first two lines (head -n 2)
+        assert_eq!(run_lines(&path, len, &["-n", "2"]), b"one\ntwo\n");
+        // skip one line, take two (sed -n '2,3p')
+        assert_eq!(run_lines(&path, len, &["-s", "1", "-n", "2"]), b"two\nthree\n");
+        // last two lines via an EOF-relative start (tail -n 2)
+        assert_eq!(run_lines(&path, len, &["-s", "-2"]), b"three\nfour");
+        // asking for more lines than exist clamps instead of panicking
+        assert_eq!(run_lines(&path, len, &["-n", "100"]), b"one\ntwo\nthree\nfour");
+    }
+
+    #[test]
+    fn line_mode_handles_trailing_newline() {
+        let dir = scratch_dir("lines-nl");
+        let path = dir.join("lines.txt");
+        // the common case: file ends in a newline
+        std::fs::write(&path, b"a\nb\nc\n").unwrap();
+        let len = path.metadata().unwrap().len();
+
+        // head -n 2
+        assert_eq!(run_lines(&path, len, &["-n", "2"]), b"a\nb\n");
+        // tail -n 1 must return the last line, not empty
+        assert_eq!(run_lines(&path, len, &["-s", "-1"]), b"c\n");
+        // tail -n 2
+        assert_eq!(run_lines(&path, len, &["-s", "-2"]), b"b\nc\n");
+        // sed -n '2,3p'
+        assert_eq!(run_lines(&path, len, &["-s", "1", "-n", "2"]), b"b\nc\n");
+        // over-request clamps to the whole file
+        assert_eq!(run_lines(&path, len, &["-n", "100"]), b"a\nb\nc\n");
+    }
+}